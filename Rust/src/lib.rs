@@ -1,9 +1,13 @@
+use futures::future::{BoxFuture, FutureExt, Shared};
 use futures::stream::{self, Stream, StreamExt};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 
 /// Error types that can occur during an API call.
 #[derive(Debug, thiserror::Error)]
@@ -20,6 +24,8 @@ pub enum APIError {
     JsonParseError(#[from] serde_json::Error),
     #[error("Stream processing error: {0}")]
     StreamError(String),
+    #[error("authentication error: {0}")]
+    AuthError(String),
 }
 
 // --- Data Structure Definitions (Corresponding to API Response and Request Payload) ---
@@ -52,6 +58,55 @@ struct Payload {
     pub model_id: Option<String>,
 }
 
+/// Parameters for a single generation request. Shared by `generate` and its
+/// variants (`generate_resilient`, `generate_handle`, `generate_collect`) so
+/// they don't each repeat the same long argument list.
+#[derive(Debug, Clone, Default)]
+pub struct GenerateRequest {
+    pub prompt: String,
+    pub img: Option<String>,
+    pub role: Option<String>,
+    pub max_tokens: u32,
+    pub timeout: u32,
+    /// Used to resume an existing session.
+    pub session_id: Option<String>,
+    /// Required for a new session, specifies the model ID to use.
+    pub model_id: Option<String>,
+}
+
+impl GenerateRequest {
+    /// Starts a new-session request. Use `.model_id(...)` to set the model,
+    /// or `.session_id(...)` instead to resume an existing session.
+    pub fn new(prompt: String, max_tokens: u32, timeout: u32) -> Self {
+        GenerateRequest {
+            prompt,
+            max_tokens,
+            timeout,
+            ..Default::default()
+        }
+    }
+
+    pub fn img(mut self, img: String) -> Self {
+        self.img = Some(img);
+        self
+    }
+
+    pub fn role(mut self, role: String) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    pub fn session_id(mut self, session_id: String) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    pub fn model_id(mut self, model_id: String) -> Self {
+        self.model_id = Some(model_id);
+        self
+    }
+}
+
 /// The base structure for the raw JSON response returned by the API.
 #[derive(Debug, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -75,6 +130,47 @@ pub enum APIResponse {
     },
 }
 
+// --- Endpoint Surface: Models, Sessions, Usage ---
+
+/// Metadata for a model available to the account, as returned by
+/// `APIClient::list_models`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ModelInfo {
+    pub model_id: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Raw response body for `GET /api/v1/models`.
+#[derive(Debug, Deserialize)]
+struct ListModelsResponse {
+    models: Vec<ModelInfo>,
+}
+
+/// Session state and history, as returned by `APIClient::get_session`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub model_id: String,
+    pub history: Vec<Delta>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Result of `APIClient::cancel_session`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CancelResult {
+    pub session_id: String,
+    pub cancelled: bool,
+}
+
+/// Aggregate account billing, in the same units as `GenerationYield::Billing`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AccountUsage {
+    pub cost: u32,
+    pub cost_per_mtk: u32,
+}
+
 // --- Client Generator Return Structure ---
 
 /// Unified data type returned to the user by the client generator (Stream).
@@ -92,65 +188,519 @@ pub enum GenerationYield {
     Billing { cost: u32, cost_per_mtk: u32 },
 }
 
-// --- API Client ---
+// --- Resilient Generation (auto-reconnect) ---
 
-/// Sintelli API Client.
-pub struct APIClient {
-    base_url: String,
+/// Backoff configuration for [`APIClient::generate_resilient`].
+///
+/// Delays grow as `base_delay * factor^attempt`, capped at `max_delay`, with
+/// up to `±jitter` (a fraction of the capped delay) applied to avoid
+/// thundering-herd reconnects.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub base_delay: Duration,
+    pub factor: f64,
+    pub max_delay: Duration,
+    pub jitter: f64,
+    pub max_retries: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            base_delay: Duration::from_millis(500),
+            factor: 2.0,
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            max_retries: 5,
+        }
+    }
+}
+
+impl RetryConfig {
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let scaled = self.base_delay.as_secs_f64() * self.factor.powi(attempt as i32);
+        let capped = scaled.min(self.max_delay.as_secs_f64());
+        let jitter_range = capped * self.jitter;
+        let jittered = capped + (rand::random::<f64>() * 2.0 - 1.0) * jitter_range;
+        Duration::from_secs_f64(jittered.max(0.0))
+    }
+}
+
+/// Boxed, pinned stream of generation output; aliased so `ResilientState`
+/// doesn't trip `clippy::type_complexity`.
+type GenerationStream = Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>;
+
+/// State threaded through the `stream::unfold` driving `generate_resilient`.
+struct ResilientState {
+    client: APIClient,
+    /// The in-flight request; `session_id`/`model_id` are updated in place
+    /// as the session is established so a reconnect resumes it.
+    request: GenerateRequest,
+    retry: RetryConfig,
+    attempt: u32,
+    watermark: Option<u32>,
+    session_emitted: bool,
+    inner: Option<GenerationStream>,
+    done: bool,
+    last_error: Option<APIError>,
+}
+
+/// What to do with a yield pulled off the current inner connection, as
+/// decided by [`ResilientState::handle_item`]. This is pure (no I/O) so it
+/// can be exercised directly in tests.
+enum ItemOutcome {
+    /// Forward this yield to the caller; the connection stays open.
+    Emit(Result<GenerationYield, APIError>),
+    /// Forward this yield to the caller and end the stream afterwards.
+    EmitAndDone(Result<GenerationYield, APIError>),
+    /// Drop this item (a suppressed `Session` or a re-delivered `Data`
+    /// below the watermark) and pull the next one.
+    Skip,
+    /// The connection ended or errored before a `Billing` yield; decide via
+    /// [`ResilientState::reconnect_decision`] whether to resume.
+    Disconnected,
+}
+
+/// Whether to resume the connection, as decided by
+/// [`ResilientState::reconnect_decision`]. Pure (no I/O, no sleeping).
+enum ReconnectDecision {
+    /// No known session to resume, or retries are exhausted.
+    GiveUp,
+    /// Resume after waiting this long.
+    RetryAfter(Duration),
+}
+
+impl ResilientState {
+    /// Applies the dedup/suppression invariants to a yield pulled from the
+    /// current connection and decides what the caller should do with it.
+    fn handle_item(&mut self, item: Option<Result<GenerationYield, APIError>>) -> ItemOutcome {
+        match item {
+            Some(Ok(GenerationYield::Session { session_id })) => {
+                self.request.session_id = Some(session_id.clone());
+                self.request.model_id = None;
+                if self.session_emitted {
+                    ItemOutcome::Skip
+                } else {
+                    self.session_emitted = true;
+                    ItemOutcome::Emit(Ok(GenerationYield::Session { session_id }))
+                }
+            }
+            Some(Ok(GenerationYield::Data { delta, step, tokens })) => {
+                if self.watermark.is_some_and(|w| step <= w) {
+                    ItemOutcome::Skip
+                } else {
+                    self.watermark = Some(step);
+                    self.attempt = 0;
+                    ItemOutcome::Emit(Ok(GenerationYield::Data { delta, step, tokens }))
+                }
+            }
+            Some(Ok(GenerationYield::Billing { cost, cost_per_mtk })) => {
+                ItemOutcome::EmitAndDone(Ok(GenerationYield::Billing { cost, cost_per_mtk }))
+            }
+            Some(Err(e @ APIError::StreamError(_))) => {
+                // The server reported a generation error; not recoverable by resuming.
+                ItemOutcome::EmitAndDone(Err(e))
+            }
+            Some(Err(e)) => {
+                self.last_error = Some(e);
+                ItemOutcome::Disconnected
+            }
+            None => {
+                self.last_error = Some(APIError::StreamError(
+                    "stream ended before completion".to_string(),
+                ));
+                ItemOutcome::Disconnected
+            }
+        }
+    }
+
+    /// Decides whether a broken connection (mid-stream or on the resume
+    /// POST itself) should be retried, advancing the backoff counter.
+    fn reconnect_decision(&mut self) -> ReconnectDecision {
+        if self.request.session_id.is_none() || self.attempt >= self.retry.max_retries {
+            ReconnectDecision::GiveUp
+        } else {
+            let delay = self.retry.delay_for(self.attempt);
+            self.attempt += 1;
+            ReconnectDecision::RetryAfter(delay)
+        }
+    }
+
+    /// Builds the payload for a reconnect attempt. Unlike the initial
+    /// connection, a reconnect is resuming the *same* in-flight generation
+    /// after a network blip rather than starting a new conversational turn,
+    /// so it omits the original `prompt`/`img` instead of resending them as
+    /// `input` on the `"resume"` payload. This assumes the server does not
+    /// require (and won't treat as a new user message) an empty `input` when
+    /// resuming an already-started session; revisit this if that turns out
+    /// not to hold.
+    fn reconnect_request(&self) -> GenerateRequest {
+        let mut request = self.request.clone();
+        request.prompt = String::new();
+        request.img = None;
+        request
+    }
+}
+
+// --- Authentication ---
+
+/// Supplies the `Authorization` header value for each request.
+///
+/// Implement this for authentication schemes other than a fixed API key,
+/// e.g. a gateway that issues short-lived tokens. See [`StaticBearer`] for
+/// the default (today's) behavior and [`RefreshingToken`] for expiring
+/// tokens fetched from a token endpoint.
+#[async_trait::async_trait]
+pub trait Auth: Send + Sync {
+    async fn header(&self) -> Result<String, APIError>;
+}
+
+/// [`Auth`] impl that always sends the same bearer token, preserving
+/// `APIClient`'s original fixed-`api_key` behavior.
+pub struct StaticBearer {
     api_key: String,
+}
+
+impl StaticBearer {
+    pub fn new(api_key: String) -> Self {
+        StaticBearer { api_key }
+    }
+}
+
+#[async_trait::async_trait]
+impl Auth for StaticBearer {
+    async fn header(&self) -> Result<String, APIError> {
+        if self.api_key.is_empty() {
+            return Err(APIError::MissingCredentials);
+        }
+        Ok(format!("Bearer {}", self.api_key))
+    }
+}
+
+/// Token response expected from the `RefreshingToken` token endpoint.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: String,
+    expires_in: u64,
+}
+
+/// Shared, clonable future resolving to a freshly-fetched token and its
+/// expiry; aliased so `TokenCache` doesn't trip `clippy::type_complexity`.
+type TokenFetch = Shared<BoxFuture<'static, Result<(String, Instant), Arc<APIError>>>>;
+
+#[derive(Default)]
+struct TokenCache {
+    token: Option<String>,
+    expires_at: Option<Instant>,
+    in_flight: Option<TokenFetch>,
+}
+
+/// [`Auth`] impl for gateways that front Sintelli with an expiring token.
+///
+/// The current token is cached alongside its expiry. When it is missing or
+/// within `refresh_window` of expiring, a new one is fetched from
+/// `token_endpoint`. Concurrent callers that observe an expired token at
+/// the same time share a single in-flight refresh (single-flight) instead
+/// of each firing their own request.
+pub struct RefreshingToken {
+    token_endpoint: String,
     http_client: Client,
+    refresh_window: Duration,
+    cache: Arc<RwLock<TokenCache>>,
 }
 
-impl APIClient {
-    /// Constructor: Initializes the API Client.
-    pub fn new(api_key: String, base_url: String) -> Self {
-        let client = Client::builder()
-            // Set a reasonable total request timeout
-            .timeout(Duration::from_secs(300))
-            .build()
-            .expect("Failed to create HTTP client");
+impl RefreshingToken {
+    pub fn new(token_endpoint: String, refresh_window: Duration) -> Self {
+        RefreshingToken {
+            token_endpoint,
+            http_client: Client::new(),
+            refresh_window,
+            cache: Arc::new(RwLock::new(TokenCache::default())),
+        }
+    }
+
+    async fn fetch_token(
+        http_client: Client,
+        token_endpoint: String,
+    ) -> Result<(String, Instant), Arc<APIError>> {
+        let response = http_client
+            .post(&token_endpoint)
+            .send()
+            .await
+            .map_err(|e| Arc::new(APIError::RequestError(e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Arc::new(APIError::ApiServerError(status, body)));
+        }
+
+        let parsed: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| Arc::new(APIError::RequestError(e)))?;
+
+        Ok((parsed.token, Instant::now() + Duration::from_secs(parsed.expires_in)))
+    }
+}
+
+#[async_trait::async_trait]
+impl Auth for RefreshingToken {
+    async fn header(&self) -> Result<String, APIError> {
+        if let Some(token) = self.fresh_token().await {
+            return Ok(format!("Bearer {}", token));
+        }
+
+        let refresh = {
+            let mut cache = self.cache.write().await;
+            // Re-check now that we hold the write lock: another caller may
+            // have just finished refreshing.
+            if let Some(token) = Self::token_if_fresh(&cache, self.refresh_window) {
+                return Ok(format!("Bearer {}", token));
+            }
+            match &cache.in_flight {
+                Some(existing) => existing.clone(),
+                None => {
+                    let http_client = self.http_client.clone();
+                    let token_endpoint = self.token_endpoint.clone();
+                    let shared = async move { Self::fetch_token(http_client, token_endpoint).await }
+                        .boxed()
+                        .shared();
+                    cache.in_flight = Some(shared.clone());
+                    shared
+                }
+            }
+        };
+
+        let result = refresh.await;
+
+        let mut cache = self.cache.write().await;
+        cache.in_flight = None;
+        match result {
+            Ok((token, expires_at)) => {
+                cache.token = Some(token.clone());
+                cache.expires_at = Some(expires_at);
+                Ok(format!("Bearer {}", token))
+            }
+            Err(e) => Err(APIError::AuthError(e.to_string())),
+        }
+    }
+}
+
+impl RefreshingToken {
+    fn token_if_fresh(cache: &TokenCache, refresh_window: Duration) -> Option<String> {
+        let (token, expires_at) = (cache.token.as_ref()?, cache.expires_at?);
+        if expires_at.saturating_duration_since(Instant::now()) > refresh_window {
+            Some(token.clone())
+        } else {
+            None
+        }
+    }
+
+    async fn fresh_token(&self) -> Option<String> {
+        let cache = self.cache.read().await;
+        Self::token_if_fresh(&cache, self.refresh_window)
+    }
+}
+
+// --- Client Builder ---
+
+/// Builder for [`APIClient`] that allows customizing the underlying
+/// transport: request timeout, extra trusted root CAs, a client identity
+/// for mutual TLS, an HTTP/HTTPS proxy, or a fully pre-built
+/// `reqwest::Client`. This is what unblocks pointing the client at
+/// internally-deployed Sintelli-compatible gateways that sit behind a
+/// private CA or corporate proxy.
+///
+/// `add_root_certificate` and `identity` depend on `reqwest`'s `native-tls`
+/// or `rustls-tls` Cargo feature (not covered by `default-tls` alone) — make
+/// sure one of those is enabled for this crate wherever its manifest lives.
+pub struct APIClientBuilder {
+    base_url: String,
+    auth: Box<dyn Auth>,
+    timeout: Duration,
+    root_certificates: Vec<reqwest::Certificate>,
+    identity: Option<reqwest::Identity>,
+    proxy: Option<reqwest::Proxy>,
+    http_client: Option<Client>,
+}
+
+impl APIClientBuilder {
+    /// Starts a builder for `base_url`, authenticating via `auth`.
+    pub fn new(base_url: String, auth: Box<dyn Auth>) -> Self {
+        APIClientBuilder {
+            base_url,
+            auth,
+            timeout: Duration::from_secs(300),
+            root_certificates: Vec::new(),
+            identity: None,
+            proxy: None,
+            http_client: None,
+        }
+    }
+
+    /// Overrides the default 300s total request timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Trusts an additional root CA certificate, e.g. for a self-hosted
+    /// gateway behind a private CA.
+    ///
+    /// Requires `reqwest`'s `native-tls` or `rustls-tls` feature.
+    pub fn add_root_certificate(mut self, cert: reqwest::Certificate) -> Self {
+        self.root_certificates.push(cert);
+        self
+    }
+
+    /// Sets a client identity (certificate + key) for mutual TLS.
+    ///
+    /// Requires `reqwest`'s `native-tls` or `rustls-tls` feature.
+    pub fn identity(mut self, identity: reqwest::Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Routes requests through an HTTP/HTTPS proxy.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Supplies a fully pre-built `reqwest::Client`, bypassing `timeout`,
+    /// `add_root_certificate`, `identity`, and `proxy` above.
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Builds the [`APIClient`].
+    ///
+    /// # Panics
+    /// Panics if the transport options (certificates, identity, proxy)
+    /// produce an invalid `reqwest::Client`.
+    pub fn build(self) -> APIClient {
+        let http_client = self.http_client.unwrap_or_else(|| {
+            let mut builder = Client::builder().timeout(self.timeout);
+            for cert in self.root_certificates {
+                builder = builder.add_root_certificate(cert);
+            }
+            if let Some(identity) = self.identity {
+                builder = builder.identity(identity);
+            }
+            if let Some(proxy) = self.proxy {
+                builder = builder.proxy(proxy);
+            }
+            builder.build().expect("Failed to create HTTP client")
+        });
 
         // Clean up base_url to ensure correct concatenation with '/api/v1/'
-        let base_url_clean = base_url.trim_end_matches('/').to_string();
+        let base_url_clean = self.base_url.trim_end_matches('/').to_string();
 
         APIClient {
             base_url: base_url_clean,
-            api_key,
-            http_client: client,
+            auth: Arc::from(self.auth),
+            http_client,
         }
     }
+}
+
+// --- Handles & Convenience Helpers ---
+
+/// Handle returned alongside the stream from [`APIClient::generate_handle`],
+/// letting the caller cancel a running generation.
+pub struct GenerationHandle {
+    token: CancellationToken,
+    client: APIClient,
+    session_id: Arc<Mutex<Option<String>>>,
+}
+
+impl GenerationHandle {
+    /// Cancels the generation. Stops the paired stream from yielding
+    /// further items and, best-effort, asks the server to stop generating
+    /// by calling [`APIClient::cancel_session`] with the request's
+    /// `session_id` when resuming, or else the one captured from the
+    /// stream's `Session` yield (no server call is made if neither is
+    /// known yet).
+    pub async fn abort(&self) {
+        self.token.cancel();
+        let session_id = self.session_id.lock().unwrap().clone();
+        if let Some(session_id) = session_id {
+            let _ = self.client.cancel_session(&session_id).await;
+        }
+    }
+}
+
+/// Aggregated result of driving a generation to completion via
+/// [`APIClient::generate_collect`].
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    pub text: String,
+    pub images: Vec<String>,
+    pub session_id: Option<String>,
+    pub tokens: u32,
+    pub cost: u32,
+    pub cost_per_mtk: u32,
+}
+
+// --- API Client ---
+
+/// Sintelli API Client.
+#[derive(Clone)]
+pub struct APIClient {
+    base_url: String,
+    auth: Arc<dyn Auth>,
+    http_client: Client,
+}
+
+impl APIClient {
+    /// Constructor: Initializes the API Client with a fixed bearer API key.
+    pub fn new(api_key: String, base_url: String) -> Self {
+        Self::with_auth(base_url, Box::new(StaticBearer::new(api_key)))
+    }
+
+    /// Initializes the API Client with a custom [`Auth`] implementation,
+    /// e.g. [`RefreshingToken`] for gateways backed by expiring tokens.
+    pub fn with_auth(base_url: String, auth: Box<dyn Auth>) -> Self {
+        APIClientBuilder::new(base_url, auth).build()
+    }
 
     /// Core generation method: Interacts with the LLM and processes streaming output.
     ///
     /// It returns a `Stream` that can iterate over the generated data chunks.
     ///
     /// # Arguments
-    /// * `prompt` - User's text prompt.
-    /// * `img` - Optional Base64 encoded image data.
-    /// * `role` - Role (defaults to "user").
-    /// * `max_tokens` - Maximum number of tokens to generate.
-    /// * `timeout` - API internal processing timeout (seconds).
-    /// * `session_id` - Optional, used to resume an existing session.
-    /// * `model_id` - Required for a new session, specifies the model ID to use.
+    /// * `request` - The [`GenerateRequest`] describing the prompt, optional
+    ///   image/role, token/timeout limits, and whether to start a new
+    ///   session (`model_id`) or resume one (`session_id`).
     ///
     /// # Returns
     /// A Result, containing a Pin<Box<dyn Stream>> wrapping the asynchronous data stream on success,
     /// or an `APIError` on failure.
     pub async fn generate(
         &self,
-        prompt: String,
-        img: Option<String>,
-        role: Option<String>,
-        max_tokens: u32,
-        timeout: u32,
-        session_id: Option<String>,
-        model_id: Option<String>,
+        request: GenerateRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>, APIError>
     {
+        let GenerateRequest {
+            prompt,
+            img,
+            role,
+            max_tokens,
+            timeout,
+            session_id,
+            model_id,
+        } = request;
+
         // 1. Input Validation
-        if self.api_key.is_empty() || prompt.is_empty() || self.base_url.is_empty() {
+        // A prompt is only mandatory when starting a new session; resuming
+        // one may legitimately carry no new user input (see
+        // `ResilientState::reconnect_request`).
+        if self.base_url.is_empty() || (session_id.is_none() && prompt.is_empty()) {
             return Err(APIError::MissingCredentials);
         }
+        let auth_header = self.auth.header().await?;
 
         // 2. Construct Request Payload
         let (session_type, session_id, model_id) = if let Some(id) = session_id {
@@ -174,13 +724,12 @@ impl APIClient {
         };
 
         let url = format!("{}/api/v1/", self.base_url);
-        let api_key = &self.api_key;
         let http_client = &self.http_client;
 
         // 3. Send Request and Get Streaming Response
         let response = http_client
             .post(url)
-            .header("Authorization", format!("Bearer {}", api_key))
+            .header("Authorization", auth_header)
             .json(&payload)
             .send()
             .await?;
@@ -279,6 +828,288 @@ impl APIClient {
                 Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>,
             >)
     }
+
+    /// Resilient variant of [`APIClient::generate`] that transparently
+    /// reconnects via session `"resume"` if the underlying byte stream
+    /// errors or ends before a [`GenerationYield::Billing`] is observed.
+    ///
+    /// Reconnects use exponential backoff with jitter (see [`RetryConfig`]).
+    /// The [`GenerationYield::Session`] yield is only ever emitted once, and
+    /// any `Data` chunk whose `step` does not advance past the highest step
+    /// already seen is dropped so callers never see duplicated text across
+    /// a reconnect. A server-reported [`APIResponse::Error`] (surfaced as
+    /// [`APIError::StreamError`]) or an exhausted retry budget ends the
+    /// stream permanently.
+    ///
+    /// Reconnects send an empty `prompt`/`img` rather than resubmitting the
+    /// original request (see `ResilientState::reconnect_request`), on the
+    /// assumption that the server treats `"resume"` as continuing the
+    /// existing generation rather than appending a new user turn.
+    ///
+    /// Arguments are identical to `generate`, plus a `retry` configuration.
+    pub async fn generate_resilient(
+        &self,
+        request: GenerateRequest,
+        retry: RetryConfig,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>, APIError>
+    {
+        // Establish the first connection eagerly so credential/model
+        // validation errors surface immediately, just like `generate`.
+        let initial = self.generate(request.clone()).await?;
+
+        let state = ResilientState {
+            client: self.clone(),
+            request,
+            retry,
+            attempt: 0,
+            watermark: None,
+            session_emitted: false,
+            inner: Some(initial),
+            done: false,
+            last_error: None,
+        };
+
+        let resilient_stream = stream::unfold(state, |mut state| async move {
+            loop {
+                if state.done {
+                    return None;
+                }
+
+                if state.inner.is_none() {
+                    match state.client.generate(state.reconnect_request()).await {
+                        Ok(s) => state.inner = Some(s),
+                        Err(e) => {
+                            // The resume POST itself failed (connection
+                            // refused, a transient 5xx, a timeout); retry it
+                            // with the same backoff/retry accounting as a
+                            // mid-stream disconnect rather than failing fast.
+                            state.last_error = Some(e);
+                            match state.reconnect_decision() {
+                                ReconnectDecision::GiveUp => {
+                                    state.done = true;
+                                    return Some((Err(state.last_error.take().unwrap()), state));
+                                }
+                                ReconnectDecision::RetryAfter(delay) => {
+                                    tokio::time::sleep(delay).await;
+                                    continue;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let item = state.inner.as_mut().unwrap().next().await;
+                match state.handle_item(item) {
+                    ItemOutcome::Emit(y) => return Some((y, state)),
+                    ItemOutcome::EmitAndDone(y) => {
+                        state.done = true;
+                        return Some((y, state));
+                    }
+                    ItemOutcome::Skip => continue,
+                    ItemOutcome::Disconnected => {
+                        state.inner = None;
+                        match state.reconnect_decision() {
+                            ReconnectDecision::GiveUp => {
+                                state.done = true;
+                                return Some((Err(state.last_error.take().unwrap()), state));
+                            }
+                            ReconnectDecision::RetryAfter(delay) => {
+                                tokio::time::sleep(delay).await;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Box::pin(resilient_stream)
+            as Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>)
+    }
+
+    /// Parses a JSON response body, surfacing non-2xx statuses as
+    /// `APIError::ApiServerError` the same way `generate` does.
+    async fn parse_response<T: serde::de::DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<T, APIError> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read response body".to_string());
+            return Err(APIError::ApiServerError(status, body));
+        }
+        Ok(response.json::<T>().await?)
+    }
+
+    /// Lists the models available to this account.
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>, APIError> {
+        if self.base_url.is_empty() {
+            return Err(APIError::MissingCredentials);
+        }
+        let auth_header = self.auth.header().await?;
+        let url = format!("{}/api/v1/models", self.base_url);
+        let response = self
+            .http_client
+            .get(url)
+            .header("Authorization", auth_header)
+            .send()
+            .await?;
+        let parsed: ListModelsResponse = Self::parse_response(response).await?;
+        Ok(parsed.models)
+    }
+
+    /// Fetches the state and history of an existing session.
+    pub async fn get_session(&self, session_id: &str) -> Result<SessionInfo, APIError> {
+        if self.base_url.is_empty() {
+            return Err(APIError::MissingCredentials);
+        }
+        let auth_header = self.auth.header().await?;
+        let url = format!(
+            "{}/api/v1/sessions/{}",
+            self.base_url,
+            utf8_percent_encode(session_id, NON_ALPHANUMERIC)
+        );
+        let response = self
+            .http_client
+            .get(url)
+            .header("Authorization", auth_header)
+            .send()
+            .await?;
+        Self::parse_response(response).await
+    }
+
+    /// Stops an in-flight generation server-side.
+    pub async fn cancel_session(&self, session_id: &str) -> Result<CancelResult, APIError> {
+        if self.base_url.is_empty() {
+            return Err(APIError::MissingCredentials);
+        }
+        let auth_header = self.auth.header().await?;
+        let url = format!(
+            "{}/api/v1/sessions/{}/cancel",
+            self.base_url,
+            utf8_percent_encode(session_id, NON_ALPHANUMERIC)
+        );
+        let response = self
+            .http_client
+            .post(url)
+            .header("Authorization", auth_header)
+            .send()
+            .await?;
+        Self::parse_response(response).await
+    }
+
+    /// Fetches aggregate billing for the account.
+    pub async fn account_usage(&self) -> Result<AccountUsage, APIError> {
+        if self.base_url.is_empty() {
+            return Err(APIError::MissingCredentials);
+        }
+        let auth_header = self.auth.header().await?;
+        let url = format!("{}/api/v1/usage", self.base_url);
+        let response = self
+            .http_client
+            .get(url)
+            .header("Authorization", auth_header)
+            .send()
+            .await?;
+        Self::parse_response(response).await
+    }
+
+    /// Like [`APIClient::generate`], but also returns a [`GenerationHandle`]
+    /// the caller can use to abort the generation early.
+    pub async fn generate_handle(
+        &self,
+        request: GenerateRequest,
+    ) -> Result<
+        (
+            GenerationHandle,
+            Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>,
+        ),
+        APIError,
+    > {
+        let token = CancellationToken::new();
+        let session_id_slot: Arc<Mutex<Option<String>>> =
+            Arc::new(Mutex::new(request.session_id.clone()));
+
+        let inner = self.generate(request).await?;
+
+        let handle = GenerationHandle {
+            token: token.clone(),
+            client: self.clone(),
+            session_id: Arc::clone(&session_id_slot),
+        };
+
+        let capture_session_id = Arc::clone(&session_id_slot);
+        let cancellable_stream = Self::race_against_cancellation(inner, token, capture_session_id);
+
+        Ok((
+            handle,
+            Box::pin(cancellable_stream)
+                as Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>,
+        ))
+    }
+
+    /// Wraps `inner` so that each poll races against `token` instead of only
+    /// checking `token.is_cancelled()` once an item has already arrived, so
+    /// `abort()` also wakes a stream that is stalled `Pending` on a slow/hung
+    /// connection. Broken out of [`APIClient::generate_handle`] so the race
+    /// itself can be exercised in a test without a real connection.
+    fn race_against_cancellation(
+        inner: Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>>,
+        token: CancellationToken,
+        capture_session_id: Arc<Mutex<Option<String>>>,
+    ) -> impl Stream<Item = Result<GenerationYield, APIError>> + Send {
+        stream::unfold(
+            (inner, token, capture_session_id),
+            |(mut inner, token, capture_session_id)| async move {
+                tokio::select! {
+                    biased;
+                    _ = token.cancelled() => None,
+                    item = inner.next() => {
+                        let item = item?;
+                        if let Ok(GenerationYield::Session { session_id }) = &item {
+                            *capture_session_id.lock().unwrap() = Some(session_id.clone());
+                        }
+                        Some((item, (inner, token, capture_session_id)))
+                    }
+                }
+            },
+        )
+    }
+
+    /// Convenience wrapper that drives [`APIClient::generate`] to its
+    /// terminal `Billing` yield and returns the aggregated [`Completion`]
+    /// instead of requiring the caller to handle the `Stream` manually.
+    pub async fn generate_collect(&self, request: GenerateRequest) -> Result<Completion, APIError> {
+        let mut stream = self.generate(request).await?;
+
+        let mut completion = Completion::default();
+        while let Some(item) = stream.next().await {
+            match item? {
+                GenerationYield::Session { session_id } => {
+                    completion.session_id = Some(session_id);
+                }
+                GenerationYield::Data { delta, tokens, .. } => {
+                    if let Some(text) = delta.text {
+                        completion.text.push_str(&text);
+                    }
+                    if let Some(image) = delta.image {
+                        completion.images.push(image);
+                    }
+                    completion.tokens = tokens;
+                }
+                GenerationYield::Billing { cost, cost_per_mtk } => {
+                    completion.cost = cost;
+                    completion.cost_per_mtk = cost_per_mtk;
+                    return Ok(completion);
+                }
+            }
+        }
+
+        Err(APIError::StreamError(
+            "stream ended before a Billing yield was observed".to_string(),
+        ))
+    }
 }
 
 // --- Tests ---
@@ -304,13 +1135,12 @@ mod tests {
         println!("Starting a new generation session...");
         let stream_result = client
             .generate(
-                "Briefly describe the characteristics of the Rust language.".to_string(),
-                None,
-                None,
-                512,
-                60,
-                None,
-                Some(TEST_MODEL_ID.to_string()),
+                GenerateRequest::new(
+                    "Briefly describe the characteristics of the Rust language.".to_string(),
+                    512,
+                    60,
+                )
+                .model_id(TEST_MODEL_ID.to_string()),
             )
             .await;
 
@@ -357,4 +1187,188 @@ mod tests {
             }
         }
     }
+
+    /// Minimal single-threaded HTTP server standing in for a token
+    /// endpoint: every connection bumps `fetch_count` and returns the same
+    /// canned token response.
+    fn spawn_fake_token_endpoint() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let fetch_count = Arc::new(AtomicUsize::new(0));
+        let server_fetch_count = Arc::clone(&fetch_count);
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                server_fetch_count.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let body = r#"{"token":"tok-1","expires_in":3600}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (format!("http://{}/token", addr), fetch_count)
+    }
+
+    #[tokio::test]
+    async fn refreshing_token_single_flights_concurrent_refreshes() {
+        let (token_endpoint, fetch_count) = spawn_fake_token_endpoint();
+        let auth = RefreshingToken::new(token_endpoint, Duration::from_secs(60));
+
+        let headers = futures::future::join_all((0..8).map(|_| auth.header())).await;
+
+        for header in &headers {
+            assert_eq!(header.as_deref().unwrap(), "Bearer tok-1");
+        }
+        assert_eq!(
+            fetch_count.load(std::sync::atomic::Ordering::SeqCst),
+            1,
+            "concurrent callers should share a single in-flight refresh"
+        );
+    }
+
+    fn resilient_test_state(session_id: Option<String>, retry: RetryConfig) -> ResilientState {
+        let mut request = GenerateRequest::new("hi".to_string(), 16, 10);
+        request.session_id = session_id;
+        ResilientState {
+            client: APIClient::new("key".to_string(), "http://localhost".to_string()),
+            request,
+            retry,
+            attempt: 0,
+            watermark: None,
+            session_emitted: false,
+            inner: None,
+            done: false,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn reconnect_request_omits_the_original_prompt_and_image() {
+        let mut state = resilient_test_state(Some("s1".to_string()), RetryConfig::default());
+        state.request.img = Some("base64".to_string());
+
+        let reconnect = state.reconnect_request();
+
+        assert_eq!(reconnect.prompt, "");
+        assert_eq!(reconnect.img, None);
+        assert_eq!(reconnect.session_id, Some("s1".to_string()));
+        // The original request is left untouched for any future reconnect.
+        assert_eq!(state.request.prompt, "hi");
+    }
+
+    #[test]
+    fn delay_for_is_bounded_by_max_delay_and_jitter() {
+        let retry = RetryConfig::default();
+        for attempt in [0, 1, 2, 10] {
+            let delay = retry.delay_for(attempt);
+            let unjittered = (retry.base_delay.as_secs_f64() * retry.factor.powi(attempt as i32))
+                .min(retry.max_delay.as_secs_f64());
+            let max_allowed = unjittered * (1.0 + retry.jitter);
+            assert!(delay.as_secs_f64() >= 0.0);
+            assert!(delay.as_secs_f64() <= max_allowed + f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn handle_item_emits_session_once_and_suppresses_repeats() {
+        let mut state = resilient_test_state(None, RetryConfig::default());
+        let first = state.handle_item(Some(Ok(GenerationYield::Session {
+            session_id: "s1".to_string(),
+        })));
+        assert!(matches!(
+            first,
+            ItemOutcome::Emit(Ok(GenerationYield::Session { .. }))
+        ));
+        let second = state.handle_item(Some(Ok(GenerationYield::Session {
+            session_id: "s1".to_string(),
+        })));
+        assert!(matches!(second, ItemOutcome::Skip));
+    }
+
+    #[test]
+    fn handle_item_drops_data_at_or_below_watermark() {
+        let mut state = resilient_test_state(None, RetryConfig::default());
+        let data = |step| {
+            Some(Ok(GenerationYield::Data {
+                delta: Delta {
+                    text: Some("x".to_string()),
+                    image: None,
+                },
+                step,
+                tokens: 1,
+            }))
+        };
+
+        assert!(matches!(state.handle_item(data(3)), ItemOutcome::Emit(_)));
+        // A re-delivered chunk at or below the watermark must be dropped.
+        assert!(matches!(state.handle_item(data(3)), ItemOutcome::Skip));
+        assert!(matches!(state.handle_item(data(2)), ItemOutcome::Skip));
+        // A chunk past the watermark is forwarded as usual.
+        assert!(matches!(state.handle_item(data(4)), ItemOutcome::Emit(_)));
+    }
+
+    #[test]
+    fn reconnect_decision_gives_up_without_a_known_session() {
+        let mut state = resilient_test_state(None, RetryConfig::default());
+        assert!(matches!(
+            state.reconnect_decision(),
+            ReconnectDecision::GiveUp
+        ));
+    }
+
+    #[test]
+    fn reconnect_decision_retries_until_budget_exhausted() {
+        let retry = RetryConfig {
+            max_retries: 2,
+            ..RetryConfig::default()
+        };
+        let mut state = resilient_test_state(Some("s1".to_string()), retry);
+
+        assert!(matches!(
+            state.reconnect_decision(),
+            ReconnectDecision::RetryAfter(_)
+        ));
+        assert_eq!(state.attempt, 1);
+
+        assert!(matches!(
+            state.reconnect_decision(),
+            ReconnectDecision::RetryAfter(_)
+        ));
+        assert_eq!(state.attempt, 2);
+
+        // Retry budget is exhausted; further attempts give up.
+        assert!(matches!(
+            state.reconnect_decision(),
+            ReconnectDecision::GiveUp
+        ));
+    }
+
+    #[tokio::test]
+    async fn race_against_cancellation_ends_a_stalled_stream_promptly() {
+        let inner: Pin<Box<dyn Stream<Item = Result<GenerationYield, APIError>> + Send>> =
+            Box::pin(stream::pending());
+        let token = CancellationToken::new();
+        let session_id = Arc::new(Mutex::new(None));
+
+        let mut cancellable =
+            APIClient::race_against_cancellation(inner, token.clone(), session_id);
+
+        token.cancel();
+
+        let next = tokio::time::timeout(Duration::from_secs(1), cancellable.next())
+            .await
+            .expect("cancellation should unblock a stalled poll instead of hanging");
+        assert!(next.is_none());
+    }
 }